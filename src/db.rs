@@ -0,0 +1,47 @@
+use crate::Error;
+
+pub type Pool = bb8::Pool<bb8_postgres::PostgresConnectionManager<tokio_postgres::NoTls>>;
+
+const MIGRATIONS: &str = "
+CREATE TABLE IF NOT EXISTS custom_prefixes (
+    guild_id BIGINT PRIMARY KEY,
+    prefix TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS showcase_messages (
+    source_message_id BIGINT PRIMARY KEY,
+    showcase_message_id BIGINT NOT NULL,
+    channel_id BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS pending_rustifications (
+    guild_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    grant_at BIGINT NOT NULL,
+    PRIMARY KEY (guild_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS macros (
+    guild_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    expansion TEXT NOT NULL,
+    PRIMARY KEY (guild_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS user_timezones (
+    user_id BIGINT PRIMARY KEY,
+    tz TEXT NOT NULL
+);
+";
+
+/// Builds the shared connection pool for `database_url` and ensures the schema it's used for
+/// exists, creating it on first boot.
+pub async fn connect(database_url: &str) -> Result<Pool, Error> {
+    let manager =
+        bb8_postgres::PostgresConnectionManager::new_from_stringlike(database_url, tokio_postgres::NoTls)?;
+    let pool = bb8::Pool::builder().build(manager).await?;
+
+    pool.get().await?.batch_execute(MIGRATIONS).await?;
+
+    Ok(pool)
+}
@@ -0,0 +1,149 @@
+use crate::{merge_output_and_errors, reply_potentially_long_text_paginated, Context, Error};
+
+/// Cached metadata fetched from godbolt.org, refreshed lazily so `targets` doesn't have to hit
+/// the network on every invocation.
+#[derive(Debug, Default)]
+pub struct GodboltMetadata {
+    pub targets: Vec<String>,
+}
+
+#[derive(serde::Serialize)]
+struct GodboltRequest<'a> {
+    source: &'a str,
+    options: GodboltOptions<'a>,
+}
+
+#[derive(serde::Serialize)]
+struct GodboltOptions<'a> {
+    #[serde(rename = "userArguments")]
+    user_arguments: &'a str,
+    filters: GodboltFilters,
+}
+
+#[derive(serde::Serialize)]
+struct GodboltFilters {
+    #[serde(rename = "binaryObject")]
+    binary_object: bool,
+}
+
+#[derive(serde::Deserialize)]
+struct GodboltResponse {
+    code: i32,
+    stdout: Vec<GodboltOutputLine>,
+    stderr: Vec<GodboltOutputLine>,
+    asm: Vec<GodboltOutputLine>,
+}
+
+#[derive(serde::Deserialize)]
+struct GodboltOutputLine {
+    text: String,
+}
+
+fn join(lines: &[GodboltOutputLine]) -> String {
+    lines.iter().map(|line| line.text.as_str()).collect::<Vec<_>>().join("\n")
+}
+
+async fn compile(ctx: Context<'_>, target: &str, flags: &str, code: &str) -> Result<GodboltResponse, Error> {
+    Ok(ctx
+        .data()
+        .http
+        .post(format!("https://godbolt.org/api/compiler/{target}/compile"))
+        .header("Accept", "application/json")
+        .json(&GodboltRequest {
+            source: code,
+            options: GodboltOptions {
+                user_arguments: flags,
+                filters: GodboltFilters { binary_object: false },
+            },
+        })
+        .send()
+        .await?
+        .json()
+        .await?)
+}
+
+/// Compiles Rust code on godbolt.org and shows the resulting assembly.
+#[poise::command(prefix_command, slash_command, track_edits, broadcast_typing, rename = "godbolt")]
+pub async fn godbolt(
+    ctx: Context<'_>,
+    #[description = "Godbolt target triple/compiler id, e.g. `rustc_nightly`"] target: Option<String>,
+    #[description = "Code to compile"] code: poise::CodeBlock,
+) -> Result<(), Error> {
+    let target = target.unwrap_or_else(|| "rustc_nightly".to_owned());
+    let response = compile(ctx, &target, "-Copt-level=3", &code.code).await?;
+
+    if response.code != 0 {
+        ctx.say(format!("```\n{}\n```", merge_output_and_errors(&join(&response.stdout), &join(&response.stderr))))
+            .await?;
+        return Ok(());
+    }
+
+    ctx.say(format!("```x86asm\n{}\n```", join(&response.asm))).await?;
+    Ok(())
+}
+
+/// Runs [llvm-mca](https://llvm.org/docs/CommandGuide/llvm-mca.html) on the given code's assembly
+/// to estimate its throughput and latency.
+#[poise::command(prefix_command, slash_command, track_edits, broadcast_typing)]
+pub async fn mca(
+    ctx: Context<'_>,
+    #[description = "Godbolt target triple/compiler id"] target: Option<String>,
+    #[description = "Code to analyze"] code: poise::CodeBlock,
+) -> Result<(), Error> {
+    let target = target.unwrap_or_else(|| "rustc_nightly".to_owned());
+    let response = compile(ctx, &target, "-Copt-level=3 --tool=mca", &code.code).await?;
+    ctx.say(format!("```\n{}\n```", merge_output_and_errors(&join(&response.stdout), &join(&response.stderr))))
+        .await?;
+    Ok(())
+}
+
+/// Compiles Rust code and shows the generated LLVM IR. Can produce long output, so it's sent
+/// paginated rather than truncated.
+#[poise::command(prefix_command, slash_command, track_edits, broadcast_typing)]
+pub async fn llvmir(
+    ctx: Context<'_>,
+    #[description = "Godbolt target triple/compiler id"] target: Option<String>,
+    #[description = "Code to compile"] code: poise::CodeBlock,
+) -> Result<(), Error> {
+    let target = target.unwrap_or_else(|| "rustc_nightly".to_owned());
+    let response = compile(ctx, &target, "--emit=llvm-ir -Copt-level=3", &code.code).await?;
+    reply_potentially_long_text_paginated(
+        ctx,
+        &merge_output_and_errors(&join(&response.stdout), &join(&response.stderr)),
+    )
+    .await
+}
+
+/// Lists godbolt.org's known compilation targets, refreshing the cache if it's empty.
+#[poise::command(prefix_command, slash_command, track_edits)]
+pub async fn targets(ctx: Context<'_>) -> Result<(), Error> {
+    #[derive(serde::Deserialize)]
+    struct Compiler {
+        id: String,
+    }
+
+    let targets = {
+        let cached = ctx.data().godbolt_metadata.lock().unwrap().targets.clone();
+        cached
+    };
+
+    let targets = if targets.is_empty() {
+        let compilers: Vec<Compiler> = ctx
+            .data()
+            .http
+            .get("https://godbolt.org/api/compilers/rust")
+            .header("Accept", "application/json")
+            .send()
+            .await?
+            .json()
+            .await?;
+        let targets: Vec<String> = compilers.into_iter().map(|c| c.id).collect();
+        ctx.data().godbolt_metadata.lock().unwrap().targets = targets.clone();
+        targets
+    } else {
+        targets
+    };
+
+    ctx.say(format!("Known targets: {}", targets.join(", "))).await?;
+    Ok(())
+}
@@ -3,14 +3,24 @@ use shuttle_poise::ShuttlePoise;
 use shuttle_secrets::SecretStore;
 
 pub mod crates;
+pub mod db;
 pub mod godbolt;
+pub mod macros;
 pub mod misc;
 pub mod moderation;
 pub mod playground;
+pub mod prefixes;
+pub mod reminders;
+pub mod rustification;
+pub mod showcase;
+pub mod timezone;
 
 type Error = Box<dyn std::error::Error + Send + Sync>;
 type Context<'a> = poise::Context<'a, Data, Error>;
 
+/// The prefix used when a guild hasn't set a custom one via `prefix add`.
+pub(crate) const DEFAULT_PREFIX: &str = "?";
+
 // const EMBED_COLOR: (u8, u8, u8) = (0xf7, 0x4c, 0x00);
 const EMBED_COLOR: (u8, u8, u8) = (0xb7, 0x47, 0x00); // slightly less saturated
 
@@ -93,23 +103,7 @@ async fn event_handler(ctx: &serenity::Context, event: &poise::Event, data: &Dat
             deleted_message_id, ..
         } => showcase::try_delete_showcase_message(ctx, data, *deleted_message_id).await?,
         poise::Event::GuildMemberAddition { new_member } => {
-            const RUSTIFICATION_DELAY: u64 = 30; // in minutes
-
-            tokio::time::sleep(std::time::Duration::from_secs(RUSTIFICATION_DELAY * 60)).await;
-
-            // Ignore errors because the user may have left already
-            let _: Result<_, _> = ctx
-                .http
-                .add_member_role(
-                    new_member.guild_id.0,
-                    new_member.user.id.0,
-                    data.rustacean_role.0,
-                    Some(&format!(
-                        "Automatically rustified after {} minutes",
-                        RUSTIFICATION_DELAY
-                    )),
-                )
-                .await;
+            rustification::queue_grant(&data.pool, new_member.guild_id, new_member.user.id).await?;
         }
         _ => {}
     }
@@ -127,6 +121,8 @@ pub struct Data {
     bot_start_time: std::time::Instant,
     http: reqwest::Client,
     godbolt_metadata: std::sync::Mutex<godbolt::GodboltMetadata>,
+    reminders: std::sync::Arc<reminders::Reminders>,
+    pool: db::Pool,
 }
 
 fn env_var<T: std::str::FromStr>(name: &str) -> Result<T, Error>
@@ -261,8 +257,125 @@ async fn reply_potentially_long_text(
     Ok(())
 }
 
+/// The most messages [`reply_potentially_long_text_paginated`] will send for one reply, so a
+/// command that produces huge output can't make the bot spam the channel without bound.
+const MAX_PAGINATED_MESSAGES: usize = 10;
+
+/// Greedily packs whole lines of `text_body` into chunks of at most `chunk_budget` chars each. A
+/// single line longer than `chunk_budget` is hard-split on a char boundary instead of being
+/// dropped.
+fn pack_into_chunks(text_body: &str, chunk_budget: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for line in text_body.lines() {
+        let mut line = line;
+        loop {
+            let separator_len = if current.is_empty() { 0 } else { 1 };
+            if current.len() + separator_len + line.len() <= chunk_budget {
+                if separator_len == 1 {
+                    current.push('\n');
+                }
+                current.push_str(line);
+                break;
+            }
+
+            if !current.is_empty() {
+                chunks.push(std::mem::take(&mut current));
+            }
+
+            if line.len() <= chunk_budget {
+                current.push_str(line);
+                break;
+            }
+
+            // A single line is longer than the budget - hard-split it on a char boundary.
+            let mut split_at = chunk_budget;
+            while !line.is_char_boundary(split_at) {
+                split_at -= 1;
+            }
+            chunks.push(line[..split_at].to_owned());
+            line = &line[split_at..];
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    if chunks.is_empty() {
+        chunks.push(" ".to_owned());
+    }
+
+    chunks
+}
+
+/// Splits `text_body` into consecutive fenced code blocks of at most Discord's 2000-char message
+/// limit, packing whole lines greedily, and sends them as a sequence of replies. Unlike
+/// [`trim_text`], nothing is dropped up to [`MAX_PAGINATED_MESSAGES`] - a single line longer than
+/// the budget is hard-split on a char boundary instead. If packing would take more than
+/// [`MAX_PAGINATED_MESSAGES`] messages, the rest is noted as omitted rather than sent, to avoid
+/// turning one command into an unbounded flood of messages.
+async fn reply_potentially_long_text_paginated(ctx: Context<'_>, text_body: &str) -> Result<(), Error> {
+    const FENCE: &str = "```";
+    // Leave room for the fences themselves (2 * 3 chars) plus their surrounding newlines.
+    const CHUNK_BUDGET: usize = 2000 - 2 * FENCE.len() - 2;
+
+    let mut chunks = pack_into_chunks(text_body, CHUNK_BUDGET);
+    let omitted = chunks.len().saturating_sub(MAX_PAGINATED_MESSAGES);
+    chunks.truncate(MAX_PAGINATED_MESSAGES);
+
+    for chunk in &chunks {
+        ctx.say(format!("{FENCE}\n{chunk}\n{FENCE}")).await?;
+    }
+
+    if omitted > 0 {
+        ctx.say(format!(
+            ":warning: Output too large: omitting the remaining {} message{}.",
+            omitted,
+            if omitted == 1 { "" } else { "s" }
+        ))
+        .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod paginated_reply_tests {
+    use super::pack_into_chunks;
+
+    #[test]
+    fn packs_short_lines_together() {
+        let chunks = pack_into_chunks("a\nb\nc", 3);
+        assert_eq!(chunks, vec!["a\nb", "c"]);
+    }
+
+    #[test]
+    fn hard_splits_a_line_longer_than_the_budget() {
+        let chunks = pack_into_chunks("abcdefgh", 3);
+        assert_eq!(chunks, vec!["abc", "def", "gh"]);
+    }
+
+    #[test]
+    fn splits_on_a_char_boundary_for_multibyte_text() {
+        // "é" is 2 bytes, so a naive byte-index split would panic or cut it in half.
+        let chunks = pack_into_chunks("éééé", 3);
+        for chunk in &chunks {
+            assert!(std::str::from_utf8(chunk.as_bytes()).is_ok());
+        }
+        assert_eq!(chunks.concat(), "éééé");
+    }
+
+    #[test]
+    fn empty_input_becomes_one_space() {
+        assert_eq!(pack_into_chunks("", 2000), vec![" ".to_owned()]);
+    }
+}
+
 #[shuttle_runtime::main]
-async fn poise(#[shuttle_secrets::Secrets] secret_store: SecretStore) -> ShuttlePoise<Data, Error> {
+async fn poise(
+    #[shuttle_secrets::Secrets] secret_store: SecretStore,
+    #[shuttle_persist::Persist] persist: shuttle_persist::PersistInstance,
+) -> ShuttlePoise<Data, Error> {
     env_logger::init();
 
     let data = Data::new(&secret_store);
@@ -279,9 +392,18 @@ async fn poise(#[shuttle_secrets::Secrets] secret_store: SecretStore) -> Shuttle
     let framework = poise::Framework::builder()
         .token(secret_store.get("DISCORD_TOKEN").unwrap())
         .setup(move |ctx, ready, f| {
+            let persist = persist.clone();
+            let database_url = database_url.clone();
             Box::pin(async move {
                 poise::builtins::register_in_guild(ctx, &f.options().commands, serenity::GuildId(data.discord_guild)).await?;
                 ctx.set_activity(serenity::ActivityData::listening("/help"));
+
+                let pool = db::connect(&database_url).await?;
+
+                let reminders = std::sync::Arc::new(reminders::Reminders::load(persist));
+                tokio::spawn(reminders::dispatch_loop(ctx.clone(), reminders.clone(), pool.clone()));
+                tokio::spawn(rustification::dispatch_loop(ctx.clone(), pool.clone(), rustacean_role));
+
                 Ok(Data {
                     bot_user_id: bot.user.id,
                     mod_role_id,
@@ -290,6 +412,8 @@ async fn poise(#[shuttle_secrets::Secrets] secret_store: SecretStore) -> Shuttle
                     bot_start_time: std::time::Instant::now(),
                     http: reqwest::Client::new(),
                     godbolt_metadata: std::sync::Mutex::new(godbolt::GodboltMetadata::default()),
+                    reminders,
+                    pool,
                 })
             })
         })
@@ -319,9 +443,18 @@ async fn poise(#[shuttle_secrets::Secrets] secret_store: SecretStore) -> Shuttle
                 misc::register(),
                 misc::uptime(),
                 misc::conradluget(),
+                reminders::remind(),
+                timezone::timezone(),
+                poise::Command {
+                    subcommands: vec![macros::macro_add(), macros::macro_remove(), macros::macro_list()],
+                    ..macros::macro_()
+                },
             ],
             prefix_options: poise::PrefixFrameworkOptions {
-                prefix: Some("?".into()),
+                prefix: Some(DEFAULT_PREFIX.into()),
+                stripped_dynamic_prefix: Some(|discord, msg, data| {
+                    Box::pin(async move { macros::resolve_stripped_prefix(discord, msg, data).await })
+                }),
                 additional_prefixes: vec![
                     poise::Prefix::Literal("🦀 "),
                     poise::Prefix::Literal("🦀"),
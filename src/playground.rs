@@ -0,0 +1,207 @@
+use crate::{merge_output_and_errors, reply_potentially_long_text, reply_potentially_long_text_paginated, Context, Error};
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum Channel {
+    Stable,
+    Beta,
+    Nightly,
+}
+
+impl Channel {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Beta => "beta",
+            Self::Nightly => "nightly",
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct PlaygroundRequest<'a> {
+    channel: &'a str,
+    edition: &'a str,
+    code: &'a str,
+    mode: &'a str,
+    crate_type: &'a str,
+    tests: bool,
+}
+
+#[derive(serde::Deserialize)]
+struct PlaygroundResponse {
+    success: bool,
+    stdout: String,
+    stderr: String,
+}
+
+async fn run_code(
+    ctx: Context<'_>,
+    code: &str,
+    channel: Channel,
+    mode: &str,
+) -> Result<PlaygroundResponse, Error> {
+    Ok(ctx
+        .data()
+        .http
+        .post("https://play.rust-lang.org/execute")
+        .json(&PlaygroundRequest {
+            channel: channel.as_str(),
+            edition: "2021",
+            code,
+            mode,
+            crate_type: "bin",
+            tests: false,
+        })
+        .send()
+        .await?
+        .json()
+        .await?)
+}
+
+/// Compiles and runs Rust code in a playground sandbox, optimized for release.
+#[poise::command(prefix_command, slash_command, track_edits, broadcast_typing)]
+pub async fn play(
+    ctx: Context<'_>,
+    #[description = "Rust channel to run the code on"] channel: Option<Channel>,
+    #[description = "Code to run"] code: poise::CodeBlock,
+) -> Result<(), Error> {
+    let response = run_code(ctx, &code.code, channel.unwrap_or(Channel::Stable), "release").await?;
+    reply_potentially_long_text(
+        ctx,
+        &merge_output_and_errors(&response.stdout, &response.stderr),
+        "\n```",
+        async { "\n```\n:warning: Output too large. Showing the first part.".to_owned() },
+    )
+    .await
+}
+
+/// Compiles and runs Rust code, showing all warnings emitted by the compiler.
+#[poise::command(prefix_command, slash_command, track_edits, broadcast_typing)]
+pub async fn playwarn(
+    ctx: Context<'_>,
+    #[description = "Rust channel to run the code on"] channel: Option<Channel>,
+    #[description = "Code to run"] code: poise::CodeBlock,
+) -> Result<(), Error> {
+    let response = run_code(ctx, &code.code, channel.unwrap_or(Channel::Stable), "debug").await?;
+    reply_potentially_long_text(
+        ctx,
+        &merge_output_and_errors(&response.stdout, &response.stderr),
+        "\n```",
+        async { "\n```\n:warning: Output too large. Showing the first part.".to_owned() },
+    )
+    .await
+}
+
+/// Compiles and runs Rust code, sending the output across several messages.
+///
+/// Unlike [`play`], output is not cut at the first message - useful when you actually need to see
+/// a long stdout/stderr. At most `MAX_PAGINATED_MESSAGES` messages are sent; anything beyond that
+/// is omitted with a warning.
+#[poise::command(prefix_command, slash_command, track_edits, broadcast_typing)]
+pub async fn eval(
+    ctx: Context<'_>,
+    #[description = "Rust channel to run the code on"] channel: Option<Channel>,
+    #[description = "Code to run"] code: poise::CodeBlock,
+) -> Result<(), Error> {
+    let response = run_code(ctx, &code.code, channel.unwrap_or(Channel::Stable), "debug").await?;
+    reply_potentially_long_text_paginated(
+        ctx,
+        &merge_output_and_errors(&response.stdout, &response.stderr),
+    )
+    .await
+}
+
+/// Runs Rust code under [Miri](https://github.com/rust-lang/miri) to catch undefined behavior.
+#[poise::command(prefix_command, slash_command, track_edits, broadcast_typing)]
+pub async fn miri(
+    ctx: Context<'_>,
+    #[description = "Code to run under Miri"] code: poise::CodeBlock,
+) -> Result<(), Error> {
+    let response = run_code(ctx, &code.code, Channel::Nightly, "miri").await?;
+    reply_potentially_long_text(
+        ctx,
+        &merge_output_and_errors(&response.stdout, &response.stderr),
+        "\n```",
+        async { "\n```\n:warning: Output too large. Showing the first part.".to_owned() },
+    )
+    .await
+}
+
+/// Expands macros in the given code, printing the resulting source. Can produce long output, so
+/// it's sent paginated rather than truncated.
+#[poise::command(prefix_command, slash_command, track_edits, broadcast_typing)]
+pub async fn expand(
+    ctx: Context<'_>,
+    #[description = "Code to expand macros in"] code: poise::CodeBlock,
+) -> Result<(), Error> {
+    let response = run_code(ctx, &code.code, Channel::Nightly, "expand-macros").await?;
+    reply_potentially_long_text_paginated(
+        ctx,
+        &merge_output_and_errors(&response.stdout, &response.stderr),
+    )
+    .await
+}
+
+/// Runs clippy on the given code, reporting its lints.
+#[poise::command(prefix_command, slash_command, track_edits, broadcast_typing)]
+pub async fn clippy(
+    ctx: Context<'_>,
+    #[description = "Code to run clippy on"] code: poise::CodeBlock,
+) -> Result<(), Error> {
+    let response = run_code(ctx, &code.code, Channel::Stable, "clippy").await?;
+    reply_potentially_long_text(
+        ctx,
+        &merge_output_and_errors(&response.stdout, &response.stderr),
+        "\n```",
+        async { "\n```\n:warning: Output too large. Showing the first part.".to_owned() },
+    )
+    .await
+}
+
+/// Formats the given code with rustfmt.
+#[poise::command(prefix_command, slash_command, track_edits, broadcast_typing)]
+pub async fn fmt(
+    ctx: Context<'_>,
+    #[description = "Code to format"] code: poise::CodeBlock,
+) -> Result<(), Error> {
+    let response = run_code(ctx, &code.code, Channel::Stable, "fmt").await?;
+    reply_potentially_long_text(
+        ctx,
+        &merge_output_and_errors(&response.stdout, &response.stderr),
+        "\n```",
+        async { "\n```\n:warning: Output too large. Showing the first part.".to_owned() },
+    )
+    .await
+}
+
+/// Runs a microbenchmark of the given code (nightly only).
+#[poise::command(prefix_command, slash_command, track_edits, broadcast_typing)]
+pub async fn microbench(
+    ctx: Context<'_>,
+    #[description = "Code to benchmark"] code: poise::CodeBlock,
+) -> Result<(), Error> {
+    let response = run_code(ctx, &code.code, Channel::Nightly, "bench").await?;
+    reply_potentially_long_text(
+        ctx,
+        &merge_output_and_errors(&response.stdout, &response.stderr),
+        "\n```",
+        async { "\n```\n:warning: Output too large. Showing the first part.".to_owned() },
+    )
+    .await
+}
+
+/// Expands the given proc-macro invocation, showing the generated code.
+#[poise::command(prefix_command, slash_command, track_edits, broadcast_typing)]
+pub async fn procmacro(
+    ctx: Context<'_>,
+    #[description = "Code containing the proc-macro invocation"] code: poise::CodeBlock,
+) -> Result<(), Error> {
+    let response = run_code(ctx, &code.code, Channel::Nightly, "expand-macros").await?;
+    reply_potentially_long_text(
+        ctx,
+        &merge_output_and_errors(&response.stdout, &response.stderr),
+        "\n```",
+        async { "\n```\n:warning: Output too large. Showing the first part.".to_owned() },
+    )
+    .await
+}
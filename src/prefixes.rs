@@ -0,0 +1,99 @@
+use crate::{Context, Data, Error};
+
+/// Looks up this guild's custom prefix, if one has been set.
+pub async fn get_prefix(data: &Data, guild_id: poise::serenity_prelude::GuildId) -> Result<Option<String>, Error> {
+    let conn = data.pool.get().await?;
+    let row = conn
+        .query_opt(
+            "SELECT prefix FROM custom_prefixes WHERE guild_id = $1",
+            &[&(guild_id.0 as i64)],
+        )
+        .await?;
+
+    Ok(row.map(|row| row.get::<_, String>("prefix")))
+}
+
+async fn set_prefix(data: &Data, guild_id: poise::serenity_prelude::GuildId, prefix: &str) -> Result<(), Error> {
+    let conn = data.pool.get().await?;
+    conn.execute(
+        "INSERT INTO custom_prefixes (guild_id, prefix) VALUES ($1, $2)
+            ON CONFLICT (guild_id) DO UPDATE SET prefix = EXCLUDED.prefix",
+        &[&(guild_id.0 as i64), &prefix],
+    )
+    .await?;
+    Ok(())
+}
+
+async fn clear_prefix(data: &Data, guild_id: poise::serenity_prelude::GuildId) -> Result<(), Error> {
+    let conn = data.pool.get().await?;
+    conn.execute(
+        "DELETE FROM custom_prefixes WHERE guild_id = $1",
+        &[&(guild_id.0 as i64)],
+    )
+    .await?;
+    Ok(())
+}
+
+/// Base command for managing this server's custom command prefix. Use one of the subcommands.
+#[poise::command(prefix_command, slash_command, rename = "prefix")]
+pub async fn prefix(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("Use `prefix add`, `prefix remove`, `prefix list`, or `prefix reset`.")
+        .await?;
+    Ok(())
+}
+
+/// Sets this server's custom command prefix.
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "add",
+    required_permissions = "MANAGE_GUILD",
+    guild_only
+)]
+pub async fn prefix_add(
+    ctx: Context<'_>,
+    #[description = "The new prefix"] prefix: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("This command only works in a server")?;
+    set_prefix(ctx.data(), guild_id, &prefix).await?;
+    ctx.say(format!("This server's prefix is now `{}`.", prefix)).await?;
+    Ok(())
+}
+
+/// Removes this server's custom command prefix, falling back to the default.
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "remove",
+    required_permissions = "MANAGE_GUILD",
+    guild_only
+)]
+pub async fn prefix_remove(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("This command only works in a server")?;
+    clear_prefix(ctx.data(), guild_id).await?;
+    ctx.say("This server's custom prefix was removed.").await?;
+    Ok(())
+}
+
+/// Shows this server's custom command prefix, if any.
+#[poise::command(prefix_command, slash_command, rename = "list", guild_only)]
+pub async fn prefix_list(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("This command only works in a server")?;
+    match get_prefix(ctx.data(), guild_id).await? {
+        Some(prefix) => ctx.say(format!("This server's custom prefix is `{}`.", prefix)).await?,
+        None => ctx.say("This server has no custom prefix set.").await?,
+    };
+    Ok(())
+}
+
+/// Alias for `prefix remove`.
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "reset",
+    required_permissions = "MANAGE_GUILD",
+    guild_only
+)]
+pub async fn prefix_reset(ctx: Context<'_>) -> Result<(), Error> {
+    prefix_remove(ctx).await
+}
@@ -0,0 +1,245 @@
+use crate::{Context, Error};
+use poise::serenity_prelude as serenity;
+
+/// How far into the future a reminder may be scheduled.
+const MAX_REMINDER_HORIZON: std::time::Duration = std::time::Duration::from_secs(60 * 60 * 24 * 365);
+
+/// How often the dispatcher checks for due reminders.
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+const PERSIST_KEY: &str = "reminders";
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Reminder {
+    pub id: u64,
+    pub user_id: serenity::UserId,
+    pub channel_id: serenity::ChannelId,
+    pub guild_id: Option<serenity::GuildId>,
+    pub body: String,
+    pub trigger_at: i64,
+}
+
+/// In-memory reminder queue, backed by [`shuttle_persist`] so it survives restarts.
+#[derive(Debug)]
+pub struct Reminders {
+    persist: shuttle_persist::PersistInstance,
+    next_id: std::sync::atomic::AtomicU64,
+    pending: std::sync::Mutex<Vec<Reminder>>,
+}
+
+impl Reminders {
+    /// Reloads all pending reminders from persistent storage.
+    pub fn load(persist: shuttle_persist::PersistInstance) -> Self {
+        let pending = persist.load::<Vec<Reminder>>(PERSIST_KEY).unwrap_or_default();
+        let next_id = pending.iter().map(|r| r.id).max().map_or(0, |id| id + 1);
+
+        Self {
+            persist,
+            next_id: std::sync::atomic::AtomicU64::new(next_id),
+            pending: std::sync::Mutex::new(pending),
+        }
+    }
+
+    fn save(&self, pending: &[Reminder]) {
+        if let Err(e) = self.persist.save(PERSIST_KEY, pending) {
+            log::warn!("Failed to persist reminders: {}", e);
+        }
+    }
+
+    fn next_id(&self) -> u64 {
+        self.next_id.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    }
+
+    fn insert(&self, reminder: Reminder) {
+        let mut pending = self.pending.lock().unwrap();
+        pending.push(reminder);
+        self.save(&pending);
+    }
+
+    /// Removes and returns every reminder that's due by `now` (Unix seconds).
+    fn take_due(&self, now: i64) -> Vec<Reminder> {
+        let mut pending = self.pending.lock().unwrap();
+        let (due, remaining) = pending
+            .drain(..)
+            .partition(|reminder| reminder.trigger_at <= now);
+        *pending = remaining;
+        self.save(&pending);
+        due
+    }
+}
+
+fn now_unix() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+/// Parses a leading run of `<number><unit>` tokens (units: `s`, `m`, `h`, `d`, `w`), e.g.
+/// `1h30m`, and returns the total duration together with whatever text follows it.
+///
+/// Returns `None` if the input doesn't start with at least one such token.
+fn parse_duration(input: &str) -> Option<(std::time::Duration, &str)> {
+    let mut rest = input;
+    let mut total_secs: u64 = 0;
+    let mut consumed_any = false;
+
+    while let Some(first_char) = rest.chars().next() {
+        if !first_char.is_ascii_digit() {
+            break;
+        }
+
+        let digits_len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        // An unparseable (overflowing) number is trailing garbage, same as an unrecognized unit
+        // below - stop parsing and return whatever was already parsed, rather than discarding it.
+        let Ok(number) = rest[..digits_len].parse::<u64>() else {
+            break;
+        };
+
+        let after_digits = &rest[digits_len..];
+        let Some(unit_char) = after_digits.chars().next() else {
+            break;
+        };
+        let unit_secs: u64 = match unit_char {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 60 * 60 * 24,
+            'w' => 60 * 60 * 24 * 7,
+            _ => break,
+        };
+
+        total_secs = total_secs.saturating_add(number.saturating_mul(unit_secs));
+        rest = &after_digits[unit_char.len_utf8()..];
+        consumed_any = true;
+    }
+
+    consumed_any.then(|| (std::time::Duration::from_secs(total_secs), rest.trim_start()))
+}
+
+#[cfg(test)]
+mod parse_duration_tests {
+    use super::parse_duration;
+
+    #[test]
+    fn parses_a_single_token() {
+        let (duration, rest) = parse_duration("30m check PR").unwrap();
+        assert_eq!(duration.as_secs(), 30 * 60);
+        assert_eq!(rest, "check PR");
+    }
+
+    #[test]
+    fn parses_multiple_tokens() {
+        let (duration, rest) = parse_duration("1h30m standup").unwrap();
+        assert_eq!(duration.as_secs(), 60 * 60 + 30 * 60);
+        assert_eq!(rest, "standup");
+    }
+
+    #[test]
+    fn rejects_input_with_no_leading_duration() {
+        assert!(parse_duration("check PR").is_none());
+    }
+
+    #[test]
+    fn stops_at_an_unrecognized_unit_but_keeps_what_parsed() {
+        let (duration, rest) = parse_duration("30m10x check PR").unwrap();
+        assert_eq!(duration.as_secs(), 30 * 60);
+        assert_eq!(rest, "10x check PR");
+    }
+
+    #[test]
+    fn stops_at_an_overflowing_number_but_keeps_what_parsed_before_it() {
+        // 30m parses fine; the following number overflows u64, so parsing should stop there and
+        // return what was already accumulated rather than failing the whole parse.
+        let (duration, rest) = parse_duration("30m99999999999999999999h standup").unwrap();
+        assert_eq!(duration.as_secs(), 30 * 60);
+        assert_eq!(rest, "99999999999999999999h standup");
+    }
+
+    #[test]
+    fn saturates_instead_of_overflowing_on_huge_but_parseable_numbers() {
+        let (duration, _) = parse_duration("99999999999999w").unwrap();
+        assert_eq!(duration, std::time::Duration::from_secs(u64::MAX));
+    }
+}
+
+/// Sets a reminder. Give a duration made up of `<number><unit>` pairs (units: `s`, `m`, `h`,
+/// `d`, `w`), then the text of the reminder, e.g. `?remind 1h30m standup`.
+#[poise::command(prefix_command, slash_command, rename = "remind")]
+pub async fn remind(
+    ctx: Context<'_>,
+    #[description = "Duration followed by the reminder text, e.g. `1h30m standup`"]
+    #[rest]
+    input: String,
+) -> Result<(), Error> {
+    let (duration, body) = match parse_duration(&input) {
+        Some((duration, body)) if !body.trim().is_empty() => (duration, body.trim().to_owned()),
+        _ => {
+            ctx.say(
+                "Please give a duration followed by the reminder text, e.g. `?remind 1h30m standup`.",
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let trigger_at = now_unix() + duration.min(MAX_REMINDER_HORIZON).as_secs() as i64;
+
+    ctx.data().reminders.insert(Reminder {
+        id: ctx.data().reminders.next_id(),
+        user_id: ctx.author().id,
+        channel_id: ctx.channel_id(),
+        guild_id: ctx.guild_id(),
+        body,
+        trigger_at,
+    });
+
+    let tz = ctx.data().user_timezone(ctx.author().id).await;
+    let trigger_local = chrono::DateTime::<chrono::Utc>::from(
+        std::time::UNIX_EPOCH + std::time::Duration::from_secs(trigger_at as u64),
+    )
+    .with_timezone(&tz);
+
+    ctx.say(format!(
+        "Got it, I'll remind you at {} ({}).",
+        trigger_local.format("%Y-%m-%d %H:%M"),
+        tz.name()
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Runs forever, periodically sending and clearing out due reminders. Spawned once from the
+/// `poise` setup closure so reminders set before a restart still fire afterwards.
+pub async fn dispatch_loop(discord: serenity::Context, reminders: std::sync::Arc<Reminders>, pool: crate::db::Pool) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        for reminder in reminders.take_due(now_unix()) {
+            if reminder.channel_id.to_channel(&discord).await.is_err() {
+                // Channel no longer resolves (e.g. deleted) - just drop the reminder.
+                continue;
+            }
+
+            let tz = crate::timezone::user_timezone(&pool, reminder.user_id).await;
+            let due_local = chrono::DateTime::<chrono::Utc>::from(
+                std::time::UNIX_EPOCH + std::time::Duration::from_secs(reminder.trigger_at as u64),
+            )
+            .with_timezone(&tz);
+
+            let message = format!(
+                "<@{}> reminder (set for {} {}): {}",
+                reminder.user_id,
+                due_local.format("%Y-%m-%d %H:%M"),
+                tz.name(),
+                reminder.body
+            );
+            if let Err(e) = reminder.channel_id.say(&discord, message).await {
+                log::warn!("Failed to send reminder {}: {}", reminder.id, e);
+            }
+        }
+    }
+}
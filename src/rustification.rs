@@ -0,0 +1,87 @@
+use crate::{db, Error};
+use poise::serenity_prelude as serenity;
+
+/// How long after joining a new member waits before being auto-rustified.
+const RUSTIFICATION_DELAY: std::time::Duration = std::time::Duration::from_secs(30 * 60);
+
+/// How often the dispatcher checks for grants that are due, same cadence as [`crate::reminders`].
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+fn now_unix() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+/// Queues `user_id` in `guild_id` for the rustacean role, to be granted after
+/// [`RUSTIFICATION_DELAY`]. Durable, so it survives a bot restart mid-wait.
+pub async fn queue_grant(
+    pool: &db::Pool,
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+) -> Result<(), Error> {
+    let grant_at = now_unix() + RUSTIFICATION_DELAY.as_secs() as i64;
+
+    let conn = pool.get().await?;
+    conn.execute(
+        "INSERT INTO pending_rustifications (guild_id, user_id, grant_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (guild_id, user_id) DO UPDATE SET grant_at = EXCLUDED.grant_at",
+        &[&(guild_id.0 as i64), &(user_id.0 as i64), &grant_at],
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn take_due(pool: &db::Pool, now: i64) -> Result<Vec<(serenity::GuildId, serenity::UserId)>, Error> {
+    let conn = pool.get().await?;
+    let rows = conn
+        .query(
+            "DELETE FROM pending_rustifications WHERE grant_at <= $1 RETURNING guild_id, user_id",
+            &[&now],
+        )
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            (
+                serenity::GuildId(row.get::<_, i64>("guild_id") as u64),
+                serenity::UserId(row.get::<_, i64>("user_id") as u64),
+            )
+        })
+        .collect())
+}
+
+/// Runs forever, granting the rustacean role to members whose wait is up. Spawned once from the
+/// `poise` setup closure, alongside [`crate::reminders::dispatch_loop`], so grants queued before
+/// a restart still apply afterwards.
+pub async fn dispatch_loop(discord: serenity::Context, pool: db::Pool, rustacean_role: serenity::RoleId) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        let due = match take_due(&pool, now_unix()).await {
+            Ok(due) => due,
+            Err(e) => {
+                log::warn!("Failed to poll pending rustifications: {}", e);
+                continue;
+            }
+        };
+
+        for (guild_id, user_id) in due {
+            // Ignore errors because the user may have left already
+            let _: Result<_, _> = discord
+                .http
+                .add_member_role(
+                    guild_id.0,
+                    user_id.0,
+                    rustacean_role.0,
+                    Some("Automatically rustified after 30 minutes"),
+                )
+                .await;
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{Data, Error};
+use poise::serenity_prelude as serenity;
+
+/// Records that `showcase_message_id` in `channel_id` is the showcase channel's repost of
+/// `source_message_id`, so it can be kept in sync later.
+pub async fn record_showcase_message(
+    data: &Data,
+    source_message_id: serenity::MessageId,
+    showcase_message_id: serenity::MessageId,
+    channel_id: serenity::ChannelId,
+) -> Result<(), Error> {
+    let conn = data.pool.get().await?;
+    conn.execute(
+        "INSERT INTO showcase_messages (source_message_id, showcase_message_id, channel_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (source_message_id) DO UPDATE SET
+                showcase_message_id = EXCLUDED.showcase_message_id,
+                channel_id = EXCLUDED.channel_id",
+        &[
+            &(source_message_id.0 as i64),
+            &(showcase_message_id.0 as i64),
+            &(channel_id.0 as i64),
+        ],
+    )
+    .await?;
+    Ok(())
+}
+
+async fn lookup(data: &Data, source_message_id: serenity::MessageId) -> Result<Option<(serenity::MessageId, serenity::ChannelId)>, Error> {
+    let conn = data.pool.get().await?;
+    let row = conn
+        .query_opt(
+            "SELECT showcase_message_id, channel_id FROM showcase_messages WHERE source_message_id = $1",
+            &[&(source_message_id.0 as i64)],
+        )
+        .await?;
+
+    Ok(row.map(|row| {
+        (
+            serenity::MessageId(row.get::<_, i64>("showcase_message_id") as u64),
+            serenity::ChannelId(row.get::<_, i64>("channel_id") as u64),
+        )
+    }))
+}
+
+/// If `source_message_id` has a tracked showcase repost, replaces the repost's content with a
+/// short "(edited)" note pointing back at the source. The source message's new content is not
+/// copied into the repost.
+pub async fn try_update_showcase_message(
+    ctx: &serenity::Context,
+    data: &Data,
+    source_message_id: serenity::MessageId,
+) -> Result<(), Error> {
+    let Some((showcase_message_id, channel_id)) = lookup(data, source_message_id).await? else {
+        return Ok(());
+    };
+
+    channel_id
+        .edit_message(ctx, showcase_message_id, |m| {
+            m.content(format!("(edited) <#{}>: see original message", source_message_id))
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// If `source_message_id` has a tracked showcase repost, deletes it and forgets the mapping.
+pub async fn try_delete_showcase_message(
+    ctx: &serenity::Context,
+    data: &Data,
+    source_message_id: serenity::MessageId,
+) -> Result<(), Error> {
+    let Some((showcase_message_id, channel_id)) = lookup(data, source_message_id).await? else {
+        return Ok(());
+    };
+
+    channel_id.delete_message(ctx, showcase_message_id).await?;
+
+    let conn = data.pool.get().await?;
+    conn.execute(
+        "DELETE FROM showcase_messages WHERE source_message_id = $1",
+        &[&(source_message_id.0 as i64)],
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use crate::{db, Context, Data, Error};
+use poise::serenity_prelude as serenity;
+
+/// Looks up `user_id`'s stored timezone, defaulting to UTC if they haven't set one.
+pub async fn user_timezone(pool: &db::Pool, user_id: serenity::UserId) -> chrono_tz::Tz {
+    lookup_timezone(pool, user_id).await.unwrap_or(chrono_tz::UTC)
+}
+
+async fn lookup_timezone(pool: &db::Pool, user_id: serenity::UserId) -> Option<chrono_tz::Tz> {
+    let conn = pool.get().await.ok()?;
+    let row = conn
+        .query_opt(
+            "SELECT tz FROM user_timezones WHERE user_id = $1",
+            &[&(user_id.0 as i64)],
+        )
+        .await
+        .ok()??;
+
+    row.get::<_, String>("tz").parse().ok()
+}
+
+impl Data {
+    /// Looks up `user_id`'s stored timezone, defaulting to UTC if they haven't set one.
+    pub async fn user_timezone(&self, user_id: serenity::UserId) -> chrono_tz::Tz {
+        user_timezone(&self.pool, user_id).await
+    }
+}
+
+/// Sets your timezone (an IANA name like `America/New_York` or `Europe/Berlin`), used to render
+/// reminder times and other timestamped replies in your local time.
+#[poise::command(prefix_command, slash_command, rename = "timezone")]
+pub async fn timezone(
+    ctx: Context<'_>,
+    #[description = "IANA timezone name, e.g. `America/New_York`"] tz: String,
+) -> Result<(), Error> {
+    let Ok(parsed): Result<chrono_tz::Tz, _> = tz.parse() else {
+        ctx.say(format!(
+            "`{}` isn't a recognized timezone. Please use an IANA name like `America/New_York` or `Europe/Berlin`.",
+            tz
+        ))
+        .await?;
+        return Ok(());
+    };
+
+    let conn = ctx.data().pool.get().await?;
+    conn.execute(
+        "INSERT INTO user_timezones (user_id, tz) VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE SET tz = EXCLUDED.tz",
+        &[&(ctx.author().id.0 as i64), &parsed.name()],
+    )
+    .await?;
+
+    ctx.say(format!("Your timezone is now set to `{}`.", parsed.name())).await?;
+    Ok(())
+}